@@ -1,6 +1,6 @@
 use std::fmt::{self, Debug, Display, Formatter};
 
-type BoxError = Box<dyn std::error::Error + Send + Sync>;
+pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 /// A set of errors that can occur during parsing multipart stream and in other
 /// operations.
@@ -59,6 +59,79 @@ pub enum Error {
     #[cfg(feature = "json")]
     #[cfg_attr(nightly, doc(cfg(feature = "json")))]
     DecodeJson(serde_json::Error),
+
+    /// No boundary found in the nested part's `Content-Type` header in
+    /// [`field.nested()`](crate::Field::nested) method.
+    NestedNoBoundary,
+
+    /// The field's `Content-Type` is not `multipart/*` in
+    /// [`field.nested()`](crate::Field::nested) method.
+    NotNestedMultipart { content_type: mime::Mime },
+
+    /// Failed to decode the field data as text in
+    /// [`field.text()`](crate::Field::text) method.
+    #[cfg(feature = "charset")]
+    #[cfg_attr(nightly, doc(cfg(feature = "charset")))]
+    DecodeText {
+        field_name: Option<String>,
+        cause: BoxError,
+    },
+
+    /// An I/O error occurred while saving a field to storage in
+    /// [`field.save()`](crate::Field::save) method.
+    #[cfg(feature = "save")]
+    #[cfg_attr(nightly, doc(cfg(feature = "save")))]
+    SaveIo(std::io::Error),
+}
+
+impl Error {
+    /// Maps this error to an [`http::StatusCode`] suitable for a response
+    /// sent back to the client.
+    ///
+    /// Errors caused by malformed or non-conforming input map to `400 Bad
+    /// Request`, errors caused by exceeding configured size limits map to
+    /// `413 Payload Too Large`, and errors caused by failures on our side
+    /// (I/O, internal locking, etc.) map to `500 Internal Server Error`.
+    #[cfg(feature = "http")]
+    #[cfg_attr(nightly, doc(cfg(feature = "http")))]
+    pub fn status_code(&self) -> http::StatusCode {
+        match self {
+            Error::UnknownField { .. }
+            | Error::IncompleteFieldData { .. }
+            | Error::IncompleteHeaders
+            | Error::ReadHeaderFailed(_)
+            | Error::DecodeHeaderName { .. }
+            | Error::DecodeHeaderValue { .. }
+            | Error::IncompleteStream
+            | Error::NoMultipart
+            | Error::NoBoundary
+            | Error::MultipleBoundaries
+            | Error::DecodeContentType(_)
+            | Error::NestedNoBoundary
+            | Error::NotNestedMultipart { .. } => http::StatusCode::BAD_REQUEST,
+            #[cfg(feature = "json")]
+            Error::DecodeJson(_) => http::StatusCode::BAD_REQUEST,
+            #[cfg(feature = "charset")]
+            Error::DecodeText { .. } => http::StatusCode::BAD_REQUEST,
+            Error::FieldSizeExceeded { .. } | Error::StreamSizeExceeded { .. } => {
+                http::StatusCode::PAYLOAD_TOO_LARGE
+            }
+            Error::StreamReadFailed(_) | Error::LockFailure => {
+                http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            #[cfg(feature = "save")]
+            Error::SaveIo(_) => http::StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Returns the [`Display`] representation of this error, for
+    /// integrators that want to send it as the response body without
+    /// matching on the `#[non_exhaustive]` variants themselves.
+    #[cfg(feature = "http")]
+    #[cfg_attr(nightly, doc(cfg(feature = "http")))]
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
 }
 
 impl Debug for Error {
@@ -102,6 +175,19 @@ impl Display for Error {
             Error::MultipleBoundaries => write!(f, "multipart boundary found multiple times in Content-Type"),
             #[cfg(feature = "json")]
             Error::DecodeJson(_) => write!(f, "failed to decode field data as JSON"),
+            Error::NestedNoBoundary => write!(f, "no boundary found in nested multipart Content-Type"),
+            Error::NotNestedMultipart { content_type } => write!(
+                f,
+                "field's Content-Type {:?} is not a nested multipart/* type",
+                content_type.as_ref()
+            ),
+            #[cfg(feature = "charset")]
+            Error::DecodeText { field_name, .. } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "failed to decode field {:?} as text", name)
+            }
+            #[cfg(feature = "save")]
+            Error::SaveIo(_) => write!(f, "failed to save field to storage"),
         }
     }
 }
@@ -116,6 +202,10 @@ impl std::error::Error for Error {
             Error::DecodeContentType(e) => Some(e),
             #[cfg(feature = "json")]
             Error::DecodeJson(e) => Some(e),
+            #[cfg(feature = "charset")]
+            Error::DecodeText { cause, .. } => Some(cause.as_ref()),
+            #[cfg(feature = "save")]
+            Error::SaveIo(e) => Some(e),
             Error::UnknownField { .. }
             | Error::IncompleteFieldData { .. }
             | Error::IncompleteHeaders
@@ -125,7 +215,9 @@ impl std::error::Error for Error {
             | Error::LockFailure
             | Error::NoMultipart
             | Error::NoBoundary
-            | Error::MultipleBoundaries => None,
+            | Error::MultipleBoundaries
+            | Error::NestedNoBoundary
+            | Error::NotNestedMultipart { .. } => None,
         }
     }
 }
@@ -0,0 +1,314 @@
+use std::sync::Arc;
+
+use bytes::{Bytes, BytesMut};
+use futures_util::stream::{Stream, StreamExt};
+use http::HeaderMap;
+use mime::Mime;
+use tokio::sync::Mutex;
+
+use crate::error::Error;
+use crate::multipart::{Multipart, MultipartState};
+
+pub(crate) fn parse_content_disposition(headers: &HeaderMap) -> (Option<String>, Option<String>) {
+    let value = match headers
+        .get(http::header::CONTENT_DISPOSITION)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(value) => value,
+        None => return (None, None),
+    };
+
+    let extract = |key: &str| -> Option<String> {
+        let needle = format!("{}=\"", key);
+        // Only match `key` as a whole parameter name, so `name="` is not
+        // found inside `filename="`.
+        let mut from = 0;
+        let start = loop {
+            let pos = value[from..].find(&needle)? + from;
+            if pos == 0 || matches!(value.as_bytes()[pos - 1], b';' | b' ' | b'\t') {
+                break pos + needle.len();
+            }
+            from = pos + needle.len();
+        };
+        let end = value[start..].find('"')? + start;
+        Some(value[start..end].to_string())
+    };
+
+    (extract("name"), extract("filename"))
+}
+
+/// A single field of a multipart stream.
+pub struct Field {
+    pub(crate) index: usize,
+    pub(crate) name: Option<String>,
+    pub(crate) file_name: Option<String>,
+    pub(crate) content_type: Option<Mime>,
+    pub(crate) headers: HeaderMap,
+    pub(crate) state: Arc<Mutex<MultipartState>>,
+    pub(crate) consumed: u64,
+    pub(crate) done: bool,
+}
+
+impl Field {
+    pub(crate) async fn read_next(
+        state: Arc<Mutex<MultipartState>>,
+    ) -> Result<Option<Field>, Error> {
+        let mut guard = state.lock().await;
+        let index = guard.next_field_idx;
+        let parsed = guard.read_next_field_headers().await?;
+        let (name, file_name, content_type, headers) = match parsed {
+            Some(parsed) => parsed,
+            None => return Ok(None),
+        };
+        guard.next_field_idx += 1;
+        drop(guard);
+
+        Ok(Some(Field {
+            index,
+            name,
+            file_name,
+            content_type,
+            headers,
+            state,
+            consumed: 0,
+            done: false,
+        }))
+    }
+
+    /// The field's name, from its `Content-Disposition` header, if present.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The file name of this field, from its `Content-Disposition` header,
+    /// if present.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// This field's `Content-Type`, if present and well-formed.
+    pub fn content_type(&self) -> Option<&Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// The raw headers of this field.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The index of this field within the enclosing multipart stream,
+    /// starting at zero.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Pulls the next chunk of this field's body, or `None` once the field
+    /// is exhausted.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let mut state = self.state.lock().await;
+        match state
+            .read_field_chunk(self.name.as_deref(), &mut self.consumed)
+            .await?
+        {
+            Some(chunk) => Ok(Some(chunk)),
+            None => {
+                self.done = true;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Consumes this field, returning a stream of its remaining body
+    /// chunks. Used internally by helpers (e.g. [`Field::nested`]) that
+    /// need to hand the rest of the field's body off to another consumer.
+    pub(crate) fn into_stream(mut self) -> impl Stream<Item = Result<Bytes, Error>> {
+        async_stream::try_stream! {
+            while let Some(chunk) = self.chunk().await? {
+                yield chunk;
+            }
+        }
+    }
+
+    /// If this field's `Content-Type` is itself `multipart/*` (as emitted
+    /// by agents that nest several parts, e.g. several attached files,
+    /// under one form field), parses the boundary from that header and
+    /// returns a fresh [`Multipart`] streaming the inner sub-fields.
+    ///
+    /// The returned `Multipart` inherits this field's remaining
+    /// stream/field size budget and byte count instead of resetting them,
+    /// so the nested stream can't exceed what the parent was already
+    /// bounded by. It stops at the parent part's closing boundary and
+    /// yields [`Error::IncompleteStream`] if the nested stream ends
+    /// before that boundary is found.
+    pub fn nested(self) -> Result<Multipart, Error> {
+        // RFC 2046 section 5.1: a body part with no `Content-Type` header
+        // defaults to `text/plain`, which is never `multipart/*`.
+        let content_type = self
+            .content_type
+            .clone()
+            .unwrap_or(mime::TEXT_PLAIN);
+
+        if content_type.type_() != mime::MULTIPART {
+            return Err(Error::NotNestedMultipart { content_type });
+        }
+
+        let boundary = content_type
+            .get_param(mime::BOUNDARY)
+            .ok_or(Error::NestedNoBoundary)?
+            .to_string();
+
+        let (stream_size_limit, stream_size_counter, field_size_limit) = {
+            let state = self
+                .state
+                .try_lock()
+                .expect("field state is not locked while the field is held");
+            (
+                state.stream_size_limit,
+                state.stream_size_counter,
+                state.field_size_limit,
+            )
+        };
+
+        let stream = self
+            .into_stream()
+            .map(|result| result.map_err(|err| Box::new(err) as crate::error::BoxError));
+
+        Ok(Multipart::nested_from_parent(
+            stream,
+            boundary,
+            stream_size_limit,
+            stream_size_counter,
+            field_size_limit,
+        ))
+    }
+
+    /// Buffers this field's whole body into a single [`Bytes`], enforcing
+    /// the per-field and overall stream size limits as chunks are
+    /// collected (so an over-limit field fails fast rather than after the
+    /// whole body has been read).
+    ///
+    /// Keeps a single-allocation fast path when the first chunk already
+    /// contains the entire body.
+    pub async fn bytes(mut self) -> Result<Bytes, Error> {
+        let first = match self.chunk().await? {
+            Some(chunk) => chunk,
+            None => return Ok(Bytes::new()),
+        };
+
+        let second = match self.chunk().await? {
+            Some(chunk) => chunk,
+            None => return Ok(first),
+        };
+
+        let mut buf = BytesMut::with_capacity(first.len() + second.len());
+        buf.extend_from_slice(&first);
+        buf.extend_from_slice(&second);
+        while let Some(chunk) = self.chunk().await? {
+            buf.extend_from_slice(&chunk);
+        }
+
+        Ok(buf.freeze())
+    }
+
+    /// Buffers this field's body and decodes it as text using the charset
+    /// declared in its `Content-Type` (e.g. `text/plain; charset=iso-8859-1`),
+    /// falling back to UTF-8 if none is declared.
+    #[cfg(feature = "charset")]
+    #[cfg_attr(nightly, doc(cfg(feature = "charset")))]
+    pub async fn text(self) -> Result<String, Error> {
+        self.text_with_charset(encoding_rs::UTF_8).await
+    }
+
+    /// Like [`Field::text`], but `default` is used as the charset when the
+    /// field's `Content-Type` doesn't declare one (or declares one
+    /// `encoding_rs` doesn't recognize).
+    #[cfg(feature = "charset")]
+    #[cfg_attr(nightly, doc(cfg(feature = "charset")))]
+    pub async fn text_with_charset(
+        self,
+        default: &'static encoding_rs::Encoding,
+    ) -> Result<String, Error> {
+        let field_name = self.name.clone();
+        let encoding = self
+            .content_type
+            .as_ref()
+            .and_then(|content_type| content_type.get_param(mime::CHARSET))
+            .and_then(|charset| encoding_rs::Encoding::for_label(charset.as_str().as_bytes()))
+            .unwrap_or(default);
+
+        let buf = self.bytes().await?;
+        let (text, _, had_errors) = encoding.decode(&buf);
+        if had_errors {
+            return Err(Error::DecodeText {
+                field_name,
+                cause: format!("invalid byte sequence for encoding {}", encoding.name()).into(),
+            });
+        }
+
+        Ok(text.into_owned())
+    }
+
+    /// Streams this field's body to storage, keeping it in memory up to a
+    /// configurable threshold and spilling to a temporary file beyond
+    /// that. Returns a [`crate::SaveBuilder`] for configuring the
+    /// threshold before running it.
+    #[cfg(feature = "save")]
+    #[cfg_attr(nightly, doc(cfg(feature = "save")))]
+    pub fn save(self) -> crate::SaveBuilder {
+        crate::SaveBuilder::new(self)
+    }
+}
+
+#[cfg(all(test, feature = "charset"))]
+mod charset_tests {
+    use futures_util::stream;
+
+    use crate::error::BoxError;
+    use crate::multipart::Multipart;
+
+    fn body_stream(body: Vec<u8>) -> impl futures_util::stream::Stream<Item = Result<bytes::Bytes, BoxError>> {
+        stream::iter(std::iter::once(Ok(bytes::Bytes::from(body))))
+    }
+
+    #[tokio::test]
+    async fn text_defaults_to_utf8() {
+        let body = "--B\r\ncontent-disposition: form-data; name=\"a\"\r\n\r\nhello\r\n--B--\r\n";
+        let mut multipart = Multipart::new(body_stream(body.into()), "B");
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        assert_eq!(field.text().await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn text_honors_declared_charset() {
+        // 0xE9 is "é" in ISO-8859-1 / Windows-1252.
+        let mut body = b"--B\r\ncontent-disposition: form-data; name=\"a\"\r\ncontent-type: text/plain; charset=iso-8859-1\r\n\r\n".to_vec();
+        body.push(0xE9);
+        body.extend_from_slice(b"\r\n--B--\r\n");
+
+        let mut multipart = Multipart::new(body_stream(body), "B");
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        assert_eq!(field.text().await.unwrap(), "\u{e9}");
+    }
+
+    #[tokio::test]
+    async fn text_respects_field_size_limit() {
+        let body = "--B\r\ncontent-disposition: form-data; name=\"a\"\r\n\r\nhello world\r\n--B--\r\n";
+        let mut multipart = Multipart::new(body_stream(body.into()), "B").with_field_size_limit(4);
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        let err = field.text().await.unwrap_err();
+        assert_eq!(
+            err,
+            crate::error::Error::FieldSizeExceeded {
+                limit: 4,
+                field_name: Some("a".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,23 @@
+//! An async parser for `multipart/form-data` content-type in Rust.
+//!
+//! ## Usage
+//!
+//! A [`Multipart`] instance is created from a stream of bytes (e.g. the
+//! body of an incoming HTTP request) plus the boundary declared in the
+//! request's `Content-Type` header. Calling
+//! [`Multipart::next_field`](crate::Multipart::next_field) repeatedly
+//! yields each [`Field`] in turn.
+
+mod error;
+mod field;
+mod multipart;
+#[cfg(feature = "save")]
+#[cfg_attr(nightly, doc(cfg(feature = "save")))]
+mod save;
+
+pub use error::Error;
+pub use field::Field;
+pub use multipart::Multipart;
+#[cfg(feature = "save")]
+#[cfg_attr(nightly, doc(cfg(feature = "save")))]
+pub use save::{SaveBuilder, SavedField};
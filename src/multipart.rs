@@ -0,0 +1,392 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures_util::stream::{Stream, StreamExt};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use tokio::sync::Mutex;
+
+use crate::error::{BoxError, Error};
+use crate::field::Field;
+
+pub(crate) type BoxStream = Pin<Box<dyn Stream<Item = Result<Bytes, BoxError>> + Send + 'static>>;
+
+pub(crate) struct MultipartState {
+    stream: BoxStream,
+    buf: BytesMut,
+    eof: bool,
+    pub(crate) boundary: String,
+    pub(crate) next_field_idx: usize,
+    pub(crate) stream_size_limit: u64,
+    pub(crate) stream_size_counter: u64,
+    pub(crate) field_size_limit: u64,
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+impl MultipartState {
+    async fn fill(&mut self) -> Result<(), Error> {
+        if self.eof {
+            return Ok(());
+        }
+
+        match self.stream.next().await {
+            Some(Ok(bytes)) => {
+                self.stream_size_counter += bytes.len() as u64;
+                if self.stream_size_counter > self.stream_size_limit {
+                    return Err(Error::StreamSizeExceeded {
+                        limit: self.stream_size_limit,
+                    });
+                }
+                self.buf.extend_from_slice(&bytes);
+                Ok(())
+            }
+            // A nested `Multipart` (see `Field::nested`) is fed its parent
+            // field's own chunks, which already carry `multer::Error`s
+            // (e.g. `IncompleteStream`). Propagate those as-is instead of
+            // re-wrapping them in `StreamReadFailed`.
+            Some(Err(cause)) => match cause.downcast::<Error>() {
+                Ok(err) => Err(*err),
+                Err(cause) => Err(Error::StreamReadFailed(cause)),
+            },
+            None => {
+                self.eof = true;
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads and parses the headers of the next field, or `None` if the
+    /// closing boundary has been reached.
+    pub(crate) async fn read_next_field_headers(
+        &mut self,
+    ) -> Result<Option<(Option<String>, Option<String>, Option<mime::Mime>, HeaderMap)>, Error>
+    {
+        let boundary_marker = format!("--{}", self.boundary);
+
+        loop {
+            let Some(marker_pos) = find(&self.buf, boundary_marker.as_bytes()) else {
+                if self.eof {
+                    return Err(Error::IncompleteHeaders);
+                }
+                self.fill().await?;
+                continue;
+            };
+
+            // Need at least two more bytes after the marker to tell a
+            // closing delimiter (`--<boundary>--`) from an opening one
+            // (`--<boundary>\r\n`).
+            let after_marker = marker_pos + boundary_marker.len();
+            if self.buf.len() < after_marker + 2 {
+                if self.eof {
+                    return Err(Error::IncompleteHeaders);
+                }
+                self.fill().await?;
+                continue;
+            }
+
+            if &self.buf[after_marker..after_marker + 2] == b"--" {
+                // Closing delimiter: the stream has no more fields. Drop
+                // everything up to and including it; nothing after it
+                // (the epilogue, if any) matters.
+                self.buf.advance(after_marker + 2);
+                return Ok(None);
+            }
+
+            if &self.buf[after_marker..after_marker + 2] != b"\r\n" {
+                return Err(Error::IncompleteHeaders);
+            }
+            let header_start = after_marker + 2;
+
+            let Some(blank_pos) = find(&self.buf[header_start..], b"\r\n\r\n") else {
+                if self.eof {
+                    return Err(Error::IncompleteHeaders);
+                }
+                self.fill().await?;
+                continue;
+            };
+            let head_end = header_start + blank_pos + 4;
+
+            let head = self.buf.split_to(head_end).freeze();
+            let head = head.slice(header_start..);
+
+            let mut raw_headers = [httparse::EMPTY_HEADER; 32];
+            let parsed = httparse::parse_headers(&head, &mut raw_headers)
+                .map_err(Error::ReadHeaderFailed)?;
+
+            let raw_headers = match parsed {
+                httparse::Status::Complete((_, headers)) => headers,
+                httparse::Status::Partial => return Err(Error::IncompleteHeaders),
+            };
+
+            let mut headers = HeaderMap::new();
+            for raw in raw_headers {
+                let name =
+                    HeaderName::from_bytes(raw.name.as_bytes()).map_err(|err| {
+                        Error::DecodeHeaderName {
+                            name: raw.name.to_string(),
+                            cause: Box::new(err),
+                        }
+                    })?;
+                let value = HeaderValue::from_bytes(raw.value).map_err(|err| {
+                    Error::DecodeHeaderValue {
+                        value: raw.value.to_vec(),
+                        cause: Box::new(err),
+                    }
+                })?;
+                headers.insert(name, value);
+            }
+
+            let content_type = headers
+                .get(http::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.parse::<mime::Mime>())
+                .transpose()
+                .map_err(Error::DecodeContentType)?;
+
+            let (name, file_name) = crate::field::parse_content_disposition(&headers);
+
+            return Ok(Some((name, file_name, content_type, headers)));
+        }
+    }
+
+    /// Reads the next chunk of the current field's body, stopping at the
+    /// next boundary marker, or `None` once the field is exhausted.
+    /// Enforces `field_size_limit` against `field_consumed` as bytes are
+    /// produced, so an over-limit field fails fast instead of after the
+    /// whole body has been buffered by the caller.
+    pub(crate) async fn read_field_chunk(
+        &mut self,
+        field_name: Option<&str>,
+        field_consumed: &mut u64,
+    ) -> Result<Option<Bytes>, Error> {
+        let marker = format!("\r\n--{}", self.boundary);
+        let field_limit = self.field_size_limit;
+
+        loop {
+            if let Some(pos) = find(&self.buf, marker.as_bytes()) {
+                if pos == 0 {
+                    return Ok(None);
+                }
+                let chunk = self.buf.split_to(pos).freeze();
+                *field_consumed += chunk.len() as u64;
+                if *field_consumed > field_limit {
+                    return Err(Error::FieldSizeExceeded {
+                        limit: field_limit,
+                        field_name: field_name.map(str::to_string),
+                    });
+                }
+                return Ok(Some(chunk));
+            }
+
+            if self.eof {
+                return Err(Error::IncompleteStream);
+            }
+
+            // Keep enough of the buffer around in case the boundary marker
+            // straddles a chunk boundary, and hand the rest out eagerly.
+            if self.buf.len() > marker.len() {
+                let take = self.buf.len() - marker.len();
+                let chunk = self.buf.split_to(take).freeze();
+                *field_consumed += chunk.len() as u64;
+                if *field_consumed > field_limit {
+                    return Err(Error::FieldSizeExceeded {
+                        limit: field_limit,
+                        field_name: field_name.map(str::to_string),
+                    });
+                }
+                return Ok(Some(chunk));
+            }
+
+            self.fill().await?;
+        }
+    }
+}
+
+/// A parser that reads a `multipart/form-data` (or other multipart) stream
+/// and yields its [`Field`]s one at a time.
+pub struct Multipart {
+    pub(crate) state: Arc<Mutex<MultipartState>>,
+}
+
+impl std::fmt::Debug for Multipart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Multipart").finish_non_exhaustive()
+    }
+}
+
+impl Multipart {
+    /// Creates a new `Multipart` from a stream of bytes and the boundary
+    /// used to separate its fields.
+    pub fn new<S>(stream: S, boundary: impl Into<String>) -> Self
+    where
+        S: Stream<Item = Result<Bytes, BoxError>> + Send + 'static,
+    {
+        Multipart {
+            state: Arc::new(Mutex::new(MultipartState {
+                stream: Box::pin(stream),
+                buf: BytesMut::new(),
+                eof: false,
+                boundary: boundary.into(),
+                next_field_idx: 0,
+                stream_size_limit: u64::MAX,
+                stream_size_counter: 0,
+                field_size_limit: u64::MAX,
+            })),
+        }
+    }
+
+    /// Creates a `Multipart` for a [`Field::nested`] sub-stream, inheriting
+    /// the parent's size limits and the parent's stream byte count so far,
+    /// rather than resetting accounting to zero. This keeps the inner
+    /// stream bounded by the same budget the outer stream was.
+    pub(crate) fn nested_from_parent<S>(
+        stream: S,
+        boundary: impl Into<String>,
+        stream_size_limit: u64,
+        stream_size_counter: u64,
+        field_size_limit: u64,
+    ) -> Self
+    where
+        S: Stream<Item = Result<Bytes, BoxError>> + Send + 'static,
+    {
+        Multipart {
+            state: Arc::new(Mutex::new(MultipartState {
+                stream: Box::pin(stream),
+                buf: BytesMut::new(),
+                eof: false,
+                boundary: boundary.into(),
+                next_field_idx: 0,
+                stream_size_limit,
+                stream_size_counter,
+                field_size_limit,
+            })),
+        }
+    }
+
+    /// Sets the maximum number of bytes a single field's body may contain.
+    /// Exceeding it fails the field with [`Error::FieldSizeExceeded`] as
+    /// soon as the excess bytes are read. Defaults to unlimited.
+    pub fn with_field_size_limit(self, limit: u64) -> Self {
+        self.map_state(|state| state.field_size_limit = limit)
+    }
+
+    /// Sets the maximum number of bytes the whole multipart stream may
+    /// contain. Exceeding it fails with [`Error::StreamSizeExceeded`] as
+    /// soon as the excess bytes are read. Defaults to unlimited.
+    pub fn with_stream_size_limit(self, limit: u64) -> Self {
+        self.map_state(|state| state.stream_size_limit = limit)
+    }
+
+    fn map_state(self, f: impl FnOnce(&mut MultipartState)) -> Self {
+        let mut state = Arc::try_unwrap(self.state)
+            .unwrap_or_else(|_| panic!("Multipart state must not be shared before configuration"))
+            .into_inner();
+        f(&mut state);
+        Multipart {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Yields the next [`Field`] in the stream, or `None` once all fields
+    /// have been consumed.
+    pub async fn next_field(&mut self) -> Result<Option<Field>, Error> {
+        Field::read_next(Arc::clone(&self.state)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+
+    fn body_stream(chunks: Vec<Vec<u8>>) -> impl Stream<Item = Result<Bytes, BoxError>> {
+        stream::iter(chunks.into_iter().map(|chunk| Ok(Bytes::from(chunk))))
+    }
+
+    fn field_body(boundary: &str, name: &str, body: &str) -> String {
+        format!(
+            "--{boundary}\r\ncontent-disposition: form-data; name=\"{name}\"\r\n\r\n{body}\r\n--{boundary}--\r\n"
+        )
+    }
+
+    #[tokio::test]
+    async fn read_field_chunk_handles_boundary_straddling_a_fill() {
+        let boundary = "X-BOUNDARY";
+        let full = field_body(boundary, "a", "hello world");
+        let bytes = full.into_bytes();
+
+        // Split the body so the boundary marker (`\r\n--X-BOUNDARY`) is cut
+        // in half across two chunks delivered by the underlying stream.
+        let marker = format!("\r\n--{boundary}");
+        let marker_start = bytes
+            .windows(marker.len())
+            .position(|w| w == marker.as_bytes())
+            .unwrap();
+        let split_at = marker_start + marker.len() / 2;
+        let (first, second) = bytes.split_at(split_at);
+
+        let stream = body_stream(vec![first.to_vec(), second.to_vec()]);
+
+        let mut multipart = Multipart::new(stream, boundary);
+        let field = multipart.next_field().await.unwrap().unwrap();
+        let body = field.bytes().await.unwrap();
+        assert_eq!(body, Bytes::from_static(b"hello world"));
+        assert!(multipart.next_field().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn field_size_limit_fails_fast() {
+        let boundary = "X-BOUNDARY";
+        let full = field_body(boundary, "a", "hello world");
+
+        let stream = body_stream(vec![full.into_bytes()]);
+        let mut multipart = Multipart::new(stream, boundary).with_field_size_limit(4);
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        let err = field.bytes().await.unwrap_err();
+        assert_eq!(
+            err,
+            Error::FieldSizeExceeded {
+                limit: 4,
+                field_name: Some("a".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn nested_rejects_field_with_no_content_type() {
+        // RFC 2046 section 5.1: a body part with no `Content-Type` defaults
+        // to `text/plain`, which is not `multipart/*`.
+        let body = b"--OUTER\r\ncontent-disposition: form-data; name=\"bundle\"\r\n\r\nplain\r\n--OUTER--\r\n".to_vec();
+        let mut multipart = Multipart::new(body_stream(vec![body]), "OUTER");
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        let err = field.nested().unwrap_err();
+        assert_eq!(
+            err,
+            Error::NotNestedMultipart {
+                content_type: mime::TEXT_PLAIN
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn nested_propagates_incomplete_stream_on_truncation() {
+        // The outer transport ends mid-body: neither the inner nor the
+        // outer closing boundary ever arrives.
+        let body = b"--OUTER\r\ncontent-disposition: form-data; name=\"bundle\"\r\ncontent-type: multipart/mixed; boundary=inner\r\n\r\n--inner\r\ncontent-disposition: form-data; name=\"x\"\r\n\r\nhello, this body runs well past any boundary-straddling holdback".to_vec();
+        let mut outer = Multipart::new(body_stream(vec![body]), "OUTER");
+        let bundle = outer.next_field().await.unwrap().unwrap();
+
+        let mut inner = bundle.nested().unwrap();
+        let x = inner.next_field().await.unwrap().unwrap();
+
+        let err = x.bytes().await.unwrap_err();
+        assert_eq!(err, Error::IncompleteStream);
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+
+use bytes::{Bytes, BytesMut};
+use tokio::io::AsyncWriteExt;
+
+use crate::error::Error;
+use crate::field::Field;
+
+/// The default number of bytes a field is allowed to occupy in memory
+/// before [`SaveBuilder`] spills it to a temporary file.
+const DEFAULT_MEMORY_THRESHOLD: usize = 32 * 1024;
+
+/// The outcome of [`SaveBuilder::into_saved`]: either the field's body fit
+/// within the configured memory threshold, or it was spilled to a
+/// temporary file on disk.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SavedField {
+    /// The field's whole body, kept in memory.
+    Memory(Bytes),
+
+    /// The path of the temporary file the field's body was spilled to,
+    /// and the number of bytes written to it.
+    File(PathBuf, u64),
+}
+
+/// Configures how [`Field::save`] streams a field's body to storage.
+///
+/// Built via [`Field::save`]; call [`SaveBuilder::memory_threshold`] to
+/// override the default spill threshold, then await
+/// [`SaveBuilder::into_saved`] to actually run it.
+pub struct SaveBuilder {
+    field: Field,
+    memory_threshold: usize,
+}
+
+impl SaveBuilder {
+    pub(crate) fn new(field: Field) -> Self {
+        SaveBuilder {
+            field,
+            memory_threshold: DEFAULT_MEMORY_THRESHOLD,
+        }
+    }
+
+    /// Sets the number of bytes the field is allowed to occupy in memory
+    /// before being spilled to a temporary file. Defaults to 32 KiB.
+    pub fn memory_threshold(mut self, threshold: usize) -> Self {
+        self.memory_threshold = threshold;
+        self
+    }
+
+    /// Streams the field's body to storage, respecting the field's
+    /// existing `FieldSizeExceeded`/`StreamSizeExceeded` limits, and
+    /// returns where it ended up.
+    pub async fn into_saved(mut self) -> Result<SavedField, Error> {
+        let mut buf = BytesMut::new();
+
+        while let Some(chunk) = self.field.chunk().await? {
+            buf.extend_from_slice(&chunk);
+            if buf.len() > self.memory_threshold {
+                return spill_to_file(self.field, buf).await;
+            }
+        }
+
+        Ok(SavedField::Memory(buf.freeze()))
+    }
+}
+
+async fn spill_to_file(mut field: Field, initial: BytesMut) -> Result<SavedField, Error> {
+    let named_file = tempfile::NamedTempFile::new().map_err(Error::SaveIo)?;
+    let (std_file, temp_path) = named_file.into_parts();
+    let mut file = tokio::fs::File::from_std(std_file);
+
+    file.write_all(&initial).await.map_err(Error::SaveIo)?;
+    let mut written = initial.len() as u64;
+
+    while let Some(chunk) = field.chunk().await? {
+        file.write_all(&chunk).await.map_err(Error::SaveIo)?;
+        written += chunk.len() as u64;
+    }
+
+    file.flush().await.map_err(Error::SaveIo)?;
+    drop(file);
+
+    let path = temp_path.keep().map_err(|err| Error::SaveIo(err.error))?;
+
+    Ok(SavedField::File(path, written))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+
+    use crate::error::BoxError;
+    use crate::multipart::Multipart;
+
+    use super::*;
+
+    fn body_stream(body: Vec<u8>) -> impl futures_util::stream::Stream<Item = Result<Bytes, BoxError>> {
+        stream::iter(std::iter::once(Ok(Bytes::from(body))))
+    }
+
+    #[tokio::test]
+    async fn save_keeps_small_field_in_memory() {
+        let body = "--B\r\ncontent-disposition: form-data; name=\"a\"\r\n\r\nhello\r\n--B--\r\n";
+        let mut multipart = Multipart::new(body_stream(body.into()), "B");
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        match field.save().memory_threshold(1024).into_saved().await.unwrap() {
+            SavedField::Memory(bytes) => assert_eq!(bytes, Bytes::from_static(b"hello")),
+            SavedField::File(..) => panic!("expected an in-memory SavedField"),
+        }
+    }
+
+    #[tokio::test]
+    async fn save_spills_large_field_to_disk() {
+        let body = "--B\r\ncontent-disposition: form-data; name=\"a\"\r\n\r\nhello world\r\n--B--\r\n";
+        let mut multipart = Multipart::new(body_stream(body.into()), "B");
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        match field.save().memory_threshold(4).into_saved().await.unwrap() {
+            SavedField::File(path, written) => {
+                assert_eq!(written, 11);
+                assert_eq!(std::fs::read(&path).unwrap(), b"hello world");
+                let _ = std::fs::remove_file(path);
+            }
+            SavedField::Memory(_) => panic!("expected a spilled-to-disk SavedField"),
+        }
+    }
+
+    #[tokio::test]
+    async fn save_enforces_field_size_limit_even_after_spilling() {
+        // Deliver the body in two chunks so the field's first chunk ("hel",
+        // within the limit) is spilled to disk before the limit is exceeded.
+        let first = b"--B\r\ncontent-disposition: form-data; name=\"a\"\r\n\r\nhello wo".to_vec();
+        let second = b"rld\r\n--B--\r\n".to_vec();
+        let stream = stream::iter(vec![
+            Ok::<_, BoxError>(Bytes::from(first)),
+            Ok(Bytes::from(second)),
+        ]);
+        let mut multipart = Multipart::new(stream, "B").with_field_size_limit(4);
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        let err = field
+            .save()
+            .memory_threshold(1)
+            .into_saved()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::FieldSizeExceeded {
+                limit: 4,
+                field_name: Some("a".to_string()),
+            }
+        );
+    }
+}